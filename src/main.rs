@@ -1,13 +1,19 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event as CrosstermEvent, KeyCode, KeyEvent,
+        KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
 use std::{
-    collections::VecDeque,
+    collections::{BTreeMap, VecDeque},
     error::Error,
-    io,
+    fs, io,
+    path::Path,
+    sync::mpsc,
+    thread,
     time::{Duration, Instant},
 };
 use tui::{
@@ -15,7 +21,7 @@ use tui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap},
     Frame, Terminal,
 };
 
@@ -96,6 +102,38 @@ enum Windows {
 enum InputMode {
     Normal,
     Editing,
+    Command,
+}
+
+/// What a window asks `run_app` to do after handling a key.
+enum Transition {
+    /// Stay on the current window.
+    Stay,
+    /// Make `Windows` the active window.
+    Switch(Windows),
+    /// Save and exit the app.
+    Quit,
+}
+
+/// A self-contained window: it owns its input handling and reports back a
+/// `Transition` instead of reaching into `run_app`'s control flow.
+trait Window {
+    fn handle_key(&self, key: KeyEvent, app: &mut App) -> Transition;
+
+    /// Draw this window's detail pane into `area`. The shared left-hand list
+    /// and notes columns are laid out by `ui`; each window owns only the pane
+    /// that distinguishes it.
+    fn render<B: Backend>(&self, f: &mut Frame<B>, app: &App, area: Rect);
+}
+
+struct ActionsWindow;
+struct NotesWindow;
+struct WriterWindow;
+
+/// Events delivered to the main loop from the input thread.
+enum Event<I> {
+    Input(I),
+    Tick,
 }
 
 #[derive(Savefile, Debug, Clone)]
@@ -103,6 +141,28 @@ struct Note {
     title: String,
     text: String,
     timestamp: String,
+    /// Category bucket this note belongs to; empty on notes saved before
+    /// categories existed, which are shown under the default "Notes" tab.
+    #[savefile_versions = "1.."]
+    category: String,
+}
+
+/// The tab label a note belongs under, defaulting uncategorised notes to "Notes".
+fn category_label(note: &Note) -> String {
+    if note.category.is_empty() {
+        "Notes".to_string()
+    } else {
+        note.category.clone()
+    }
+}
+
+/// Derive a category from the first `#tag` on the title line, or "Notes".
+fn derive_category(title: &str) -> String {
+    title
+        .split_whitespace()
+        .find(|w| w.starts_with('#') && w.len() > 1)
+        .map(|w| w.trim_start_matches('#').to_string())
+        .unwrap_or_else(|| "Notes".to_string())
 }
 
 #[derive(Savefile, Debug)]
@@ -137,13 +197,51 @@ struct NoteList {
     stateful: StatefulList<Note>,
 }
 
+#[derive(Clone)]
+struct TabsState {
+    titles: Vec<String>,
+    index: usize,
+}
+
+impl TabsState {
+    fn new() -> TabsState {
+        TabsState {
+            titles: vec![],
+            index: 0,
+        }
+    }
+
+    fn next(&mut self) {
+        if !self.titles.is_empty() {
+            self.index = (self.index + 1) % self.titles.len();
+        }
+    }
+
+    fn previous(&mut self) {
+        if !self.titles.is_empty() {
+            self.index = (self.index + self.titles.len() - 1) % self.titles.len();
+        }
+    }
+}
+
 #[derive(Clone)]
 struct ViewerAction<'a> {
     stateful: StatefulList<(&'a str, usize)>,
     input_mode: InputMode,
     input: String,
+    /// Byte offset of the caret into `input`, always kept on a char boundary.
+    cursor: usize,
+    /// Bounded history of prior buffers for undo/redo.
+    undo_stack: VecDeque<String>,
+    redo_stack: VecDeque<String>,
+    /// Whether the current run of single-character insertions is still being
+    /// coalesced into one undo group.
+    coalescing: bool,
 }
 
+/// Maximum number of buffer snapshots kept on the undo history.
+const UNDO_LIMIT: usize = 100;
+
 impl ViewerAction<'_> {
     fn new(actions: Vec<&str>) -> ViewerAction {
         let mut x: usize = 0;
@@ -158,9 +256,174 @@ impl ViewerAction<'_> {
         ViewerAction {
             input_mode: InputMode::Normal,
             input: String::new(),
+            cursor: 0,
+            undo_stack: VecDeque::new(),
+            redo_stack: VecDeque::new(),
+            coalescing: false,
             stateful: StatefulList::with_items(vec),
         }
     }
+
+    /// Record the current buffer on the undo history and drop the redo branch.
+    fn snapshot(&mut self) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(self.input.clone());
+        self.redo_stack.clear();
+    }
+
+    /// End the current insertion group, so the next edit opens a new one.
+    fn break_group(&mut self) {
+        self.coalescing = false;
+    }
+
+    /// Insert a character at the caret and step over it.
+    fn insert(&mut self, c: char) {
+        if c == '\n' {
+            // Newlines always start a fresh undo group.
+            self.snapshot();
+            self.coalescing = false;
+        } else if !self.coalescing {
+            // First keystroke of a run: snapshot once, then coalesce the rest.
+            self.snapshot();
+            self.coalescing = true;
+        }
+        self.input.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Delete the character before the caret, if any.
+    fn backspace(&mut self) {
+        if self.cursor == 0 {
+            return;
+        }
+        self.snapshot();
+        self.coalescing = false;
+        let prev = self.input[..self.cursor]
+            .chars()
+            .next_back()
+            .map(|c| c.len_utf8())
+            .unwrap_or(0);
+        self.cursor -= prev;
+        self.input.remove(self.cursor);
+    }
+
+    /// Swap the current buffer for the most recent undo snapshot.
+    fn undo(&mut self) {
+        if let Some(prev) = self.undo_stack.pop_back() {
+            self.redo_stack.push_back(self.input.clone());
+            self.input = prev;
+            self.cursor = self.cursor.min(self.input.len());
+            self.snap_to_boundary();
+            self.coalescing = false;
+        }
+    }
+
+    /// Re-apply the most recently undone buffer.
+    fn redo(&mut self) {
+        if let Some(next) = self.redo_stack.pop_back() {
+            self.undo_stack.push_back(self.input.clone());
+            self.input = next;
+            self.cursor = self.cursor.min(self.input.len());
+            self.snap_to_boundary();
+            self.coalescing = false;
+        }
+    }
+
+    fn move_left(&mut self) {
+        self.break_group();
+        if let Some(c) = self.input[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    fn move_right(&mut self) {
+        self.break_group();
+        if let Some(c) = self.input[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    /// Byte offset of the first character on the caret's line.
+    fn line_start(&self) -> usize {
+        self.input[..self.cursor]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0)
+    }
+
+    /// Byte offset of the line break ending the caret's line (or the end).
+    fn line_end(&self) -> usize {
+        self.input[self.cursor..]
+            .find('\n')
+            .map(|i| self.cursor + i)
+            .unwrap_or_else(|| self.input.len())
+    }
+
+    /// Home (the `0` line motion): jump to the start of the current line.
+    fn home(&mut self) {
+        self.break_group();
+        self.cursor = self.line_start();
+    }
+
+    /// End (the `$` line motion): jump to the end of the current line.
+    fn end(&mut self) {
+        self.break_group();
+        self.cursor = self.line_end();
+    }
+
+    /// The `^` line motion: jump to the first non-whitespace character on the
+    /// current line, falling back to the line end for a blank line.
+    fn first_non_whitespace(&mut self) {
+        self.break_group();
+        let start = self.line_start();
+        let end = self.line_end();
+        self.cursor = self.input[start..end]
+            .char_indices()
+            .find(|(_, c)| !c.is_whitespace())
+            .map(|(i, _)| start + i)
+            .unwrap_or(end);
+    }
+
+    fn move_up(&mut self) {
+        self.break_group();
+        let col = self.cursor - self.line_start();
+        let start = self.line_start();
+        if start == 0 {
+            self.cursor = 0;
+            return;
+        }
+        let prev_start = self.input[..start - 1].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let prev_len = (start - 1) - prev_start;
+        self.cursor = prev_start + col.min(prev_len);
+        self.snap_to_boundary();
+    }
+
+    fn move_down(&mut self) {
+        self.break_group();
+        let col = self.cursor - self.line_start();
+        let end = self.line_end();
+        if end == self.input.len() {
+            self.cursor = end;
+            return;
+        }
+        let next_start = end + 1;
+        let next_end = self.input[next_start..]
+            .find('\n')
+            .map(|i| next_start + i)
+            .unwrap_or_else(|| self.input.len());
+        let next_len = next_end - next_start;
+        self.cursor = next_start + col.min(next_len);
+        self.snap_to_boundary();
+    }
+
+    /// Nudge the caret back onto a char boundary after a column-based move.
+    fn snap_to_boundary(&mut self) {
+        while self.cursor > 0 && !self.input.is_char_boundary(self.cursor) {
+            self.cursor -= 1;
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -169,16 +432,18 @@ struct App<'a> {
     state_notes: NoteList,
     viewer_actions: ViewerAction<'a>,
     active_window: Windows,
+    editing_index: Option<usize>,
+    tabs: TabsState,
 }
 
 impl<'a> App<'a> {
     fn new() -> App<'a> {
-        let saved_notes: SavedNotes = match load_file("saved-notes.bin", 0) {
+        let saved_notes: SavedNotes = match load_file("saved-notes.bin", 1) {
             Ok(notes) => notes,
             Err(_) => SavedNotes { notes: vec![] },
         };
 
-        let mut menu_actions = MenuAction::new(vec!["New note", "Quit"]);
+        let mut menu_actions = MenuAction::new(vec!["New note", "Export", "Import", "Quit"]);
         menu_actions.stateful.select_first();
 
         let state_notes = NoteList {
@@ -187,18 +452,22 @@ impl<'a> App<'a> {
 
         let viewer_actions = ViewerAction::new(vec!["Start writing", "Cancel", "Save"]);
 
-        App {
+        let mut app = App {
             menu_actions,
             state_notes,
             viewer_actions,
             active_window: Windows::ACTIONS,
-        }
+            editing_index: None,
+            tabs: TabsState::new(),
+        };
+        app.refresh_tabs();
+        app
     }
 
     fn quit(&mut self) -> io::Result<()> {
         let notes: Vec<Note> = self.state_notes.stateful.items.drain(..).collect();
 
-        save_file("saved-notes.bin", 0, &SavedNotes { notes }).unwrap();
+        save_file("saved-notes.bin", 1, &SavedNotes { notes }).unwrap();
 
         return Ok(());
     }
@@ -210,10 +479,509 @@ impl<'a> App<'a> {
     fn remove_note(&mut self, index: usize) {
         self.state_notes.stateful.items.remove(index);
     }
+
+    /// Group note indices by their category label, in sorted category order.
+    fn grouped(&self) -> BTreeMap<String, Vec<usize>> {
+        let mut map: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (i, note) in self.state_notes.stateful.items.iter().enumerate() {
+            map.entry(category_label(note)).or_default().push(i);
+        }
+        map
+    }
+
+    /// Rebuild the tab titles from the categories currently in use, keeping the
+    /// selected index within range.
+    fn refresh_tabs(&mut self) {
+        let mut titles: Vec<String> = self.grouped().into_keys().collect();
+        if titles.is_empty() {
+            titles.push("Notes".to_string());
+        }
+        if self.tabs.index >= titles.len() {
+            self.tabs.index = titles.len() - 1;
+        }
+        self.tabs.titles = titles;
+    }
+
+    /// The category label of the currently selected tab.
+    fn current_category(&self) -> String {
+        self.tabs
+            .titles
+            .get(self.tabs.index)
+            .cloned()
+            .unwrap_or_else(|| "Notes".to_string())
+    }
+
+    /// Indices into the note list that belong to the selected tab.
+    fn visible_indices(&self) -> Vec<usize> {
+        self.grouped()
+            .get(&self.current_category())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Write every note into the `notes/` directory as a Markdown file, a
+    /// human-readable mirror of `saved-notes.bin`.
+    fn export_notes(&self) -> io::Result<()> {
+        let dir = Path::new("notes");
+        fs::create_dir_all(dir)?;
+        for note in &self.state_notes.stateful.items {
+            let body = format!(
+                "# {}\n\n## {}\n\n{}\n",
+                note.title, note.timestamp, note.text
+            );
+            let filename = format!("{}-{}.md", slugify(&note.title), slugify(&note.timestamp));
+            fs::write(dir.join(filename), body)?;
+        }
+        Ok(())
+    }
+
+    /// Reconstruct notes from the Markdown files in the `notes/` directory,
+    /// parsing the leading `# ` line as the title and the rest as the body.
+    fn import_notes(&mut self) -> io::Result<()> {
+        let dir = Path::new("notes");
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            let contents = fs::read_to_string(&path)?;
+            let note = parse_markdown(&contents);
+            // Reconcile against the existing set so repeated imports stay
+            // idempotent: overwrite a note with the same (title, timestamp),
+            // otherwise append it.
+            match self
+                .state_notes
+                .stateful
+                .items
+                .iter()
+                .position(|n| n.title == note.title && n.timestamp == note.timestamp)
+            {
+                Some(index) => self.state_notes.stateful.items[index] = note,
+                None => self.add_note(note),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Turn arbitrary text into a lowercase, dash-separated filename fragment.
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    let mut prev_dash = false;
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+            prev_dash = false;
+        } else if !prev_dash && !slug.is_empty() {
+            slug.push('-');
+            prev_dash = true;
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        "note".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Build a `Note` from an exported Markdown document.
+fn parse_markdown(contents: &str) -> Note {
+    let mut title = "New note".to_string();
+    let mut timestamp = String::new();
+
+    // Consume only the leading header block (`#` title then optional `##`
+    // timestamp); everything after it is body, so a heading in the note text
+    // survives the round-trip intact.
+    let mut lines = contents.lines().peekable();
+    if let Some(rest) = lines.peek().and_then(|l| l.strip_prefix("# ")) {
+        title = rest.trim().to_string();
+        lines.next();
+    }
+    // `export_notes` puts a blank line between the title and the timestamp.
+    while lines.peek().map_or(false, |l| l.trim().is_empty()) {
+        lines.next();
+    }
+    if let Some(rest) = lines.peek().and_then(|l| l.strip_prefix("## ")) {
+        timestamp = rest.trim().to_string();
+        lines.next();
+    }
+
+    let body: Vec<&str> = lines.collect();
+    let text = body.join("\n").trim().to_string();
+    let category = derive_category(&title);
+
+    Note {
+        title,
+        text,
+        timestamp,
+        category,
+    }
+}
+
+impl Window for ActionsWindow {
+    fn handle_key(&self, key: KeyEvent, app: &mut App) -> Transition {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => Transition::Quit,
+            KeyCode::Down => {
+                app.menu_actions.stateful.next();
+                Transition::Stay
+            }
+            KeyCode::Up => {
+                app.menu_actions.stateful.previous();
+                Transition::Stay
+            }
+            KeyCode::Right => {
+                if app.state_notes.stateful.items.len() > 0 {
+                    app.refresh_tabs();
+                    app.state_notes.stateful.select_first();
+                    app.menu_actions.stateful.unselect();
+                    Transition::Switch(Windows::NOTES)
+                } else {
+                    Transition::Stay
+                }
+            }
+            KeyCode::Enter => {
+                if let Some(current) = app.menu_actions.stateful.selected() {
+                    match current {
+                        0 => {
+                            app.viewer_actions.input.clear();
+                            app.viewer_actions.cursor = 0;
+                            app.viewer_actions.undo_stack.clear();
+                            app.viewer_actions.redo_stack.clear();
+                            app.viewer_actions.coalescing = false;
+                            app.editing_index = None;
+                            app.viewer_actions.stateful.select_first();
+                            Transition::Switch(Windows::WRITER)
+                        }
+                        1 => {
+                            // Mirror every note out to the human-readable
+                            // Markdown directory. Failures are non-fatal.
+                            let _ = app.export_notes();
+                            Transition::Stay
+                        }
+                        2 => {
+                            let _ = app.import_notes();
+                            app.refresh_tabs();
+                            Transition::Stay
+                        }
+                        3 => Transition::Quit,
+                        _ => Transition::Stay,
+                    }
+                } else {
+                    Transition::Stay
+                }
+            }
+            _ => Transition::Stay,
+        }
+    }
+
+    fn render<B: Backend>(&self, f: &mut Frame<B>, _app: &App, area: Rect) {
+        let text = vec![
+            Spans::from(Span::styled(
+                "How to navigate the app:",
+                Style::default().fg(Color::Red),
+            )),
+            Spans::from(""),
+            Spans::from("Use the up and down arrow keys to scroll the lists"),
+            Spans::from(
+                "Use the left and right arrow keys to switch from the Action and Notes screen",
+            ),
+            Spans::from("Press enter to press a button"),
+            Spans::from(""),
+            Spans::from(Span::styled(
+                "Press 'q' to quit the app or use the quit button",
+                Style::default().fg(Color::Red),
+            )),
+        ];
+
+        let paragraph = Paragraph::new(text)
+            .style(Style::default())
+            .wrap(Wrap { trim: true })
+            .block(create_block("Info".to_string()))
+            .alignment(tui::layout::Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+}
+
+impl Window for NotesWindow {
+    fn handle_key(&self, key: KeyEvent, app: &mut App) -> Transition {
+        // The selection index is a position within the currently visible tab.
+        let visible = app.visible_indices();
+        match key.code {
+            KeyCode::Char('q') => Transition::Quit,
+            KeyCode::Up => {
+                if let Some(sel) = app.state_notes.stateful.selected() {
+                    if !visible.is_empty() {
+                        let i = if sel == 0 { visible.len() - 1 } else { sel - 1 };
+                        app.state_notes.stateful.state.select(Some(i));
+                    }
+                }
+                Transition::Stay
+            }
+            KeyCode::Down => {
+                if let Some(sel) = app.state_notes.stateful.selected() {
+                    if !visible.is_empty() {
+                        let i = if sel >= visible.len() - 1 { 0 } else { sel + 1 };
+                        app.state_notes.stateful.state.select(Some(i));
+                    }
+                }
+                Transition::Stay
+            }
+            KeyCode::Tab => {
+                app.tabs.next();
+                app.state_notes.stateful.select_first();
+                Transition::Stay
+            }
+            KeyCode::BackTab => {
+                app.tabs.previous();
+                app.state_notes.stateful.select_first();
+                Transition::Stay
+            }
+            KeyCode::Left => {
+                app.menu_actions.stateful.select_first();
+                app.state_notes.stateful.unselect();
+                Transition::Switch(Windows::ACTIONS)
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(sel) = app.state_notes.stateful.selected() {
+                    if let Some(&real) = visible.get(sel) {
+                        app.remove_note(real);
+                        app.refresh_tabs();
+                        // Keep the selection on a valid row. A tab whose last
+                        // note was deleted is dropped and the tab now at the
+                        // same index (or the last tab) is shown; once no notes
+                        // are left at all,
+                        // fall back to the actions menu.
+                        let remaining = app.visible_indices();
+                        if remaining.is_empty() {
+                            app.state_notes.stateful.unselect();
+                            app.menu_actions.stateful.select_first();
+                            return Transition::Switch(Windows::ACTIONS);
+                        }
+                        let i = sel.min(remaining.len() - 1);
+                        app.state_notes.stateful.state.select(Some(i));
+                    }
+                }
+                Transition::Stay
+            }
+            KeyCode::Char('e') | KeyCode::Enter => {
+                if let Some(sel) = app.state_notes.stateful.selected() {
+                    if let Some(&real) = visible.get(sel) {
+                        let note = &app.state_notes.stateful.items[real];
+                        app.viewer_actions.input = format!("{}\n{}", note.title, note.text);
+                        app.viewer_actions.cursor = app.viewer_actions.input.len();
+                        app.viewer_actions.undo_stack.clear();
+                        app.viewer_actions.redo_stack.clear();
+                        app.viewer_actions.coalescing = false;
+                        app.editing_index = Some(real);
+                        app.viewer_actions.stateful.select_first();
+                        app.state_notes.stateful.unselect();
+                        return Transition::Switch(Windows::WRITER);
+                    }
+                }
+                Transition::Stay
+            }
+            _ => Transition::Stay,
+        }
+    }
+
+    fn render<B: Backend>(&self, f: &mut Frame<B>, app: &App, area: Rect) {
+        let visible = app.visible_indices();
+        let selected = app
+            .state_notes
+            .stateful
+            .selected()
+            .and_then(|sel| visible.get(sel).copied())
+            .unwrap_or(0);
+        let note = &app.state_notes.stateful.items[selected];
+
+        let paragraph = Paragraph::new(String::from(&note.text))
+            .style(Style::default())
+            .wrap(Wrap { trim: true })
+            .block(create_block(String::from(&note.title)))
+            .alignment(tui::layout::Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+}
+
+impl Window for WriterWindow {
+    fn handle_key(&self, key: KeyEvent, app: &mut App) -> Transition {
+        match app.viewer_actions.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Enter => {
+                    if let Some(current) = app.viewer_actions.stateful.selected() {
+                        match current {
+                            0 => {
+                                app.viewer_actions.input_mode = InputMode::Editing;
+                                Transition::Stay
+                            }
+                            1 => {
+                                app.viewer_actions.input.clear();
+                                app.viewer_actions.cursor = 0;
+                                app.viewer_actions.stateful.unselect();
+                                app.editing_index = None;
+                                Transition::Switch(Windows::ACTIONS)
+                            }
+                            2 => {
+                                let dt = Utc::now();
+                                let timestamp = dt.format("%F %T").to_string();
+
+                                let text: String = app.viewer_actions.input.drain(..).collect();
+                                let mut lines: VecDeque<&str> = text.split('\n').collect();
+
+                                let title = match lines.pop_front() {
+                                    Some(t) => String::from(t),
+                                    None => "New note".to_string(),
+                                };
+
+                                let text: String = Vec::from(lines).join("\n");
+
+                                let category = derive_category(&title);
+
+                                let note = Note {
+                                    title,
+                                    text,
+                                    timestamp,
+                                    category,
+                                };
+
+                                // Overwrite the original note when editing an
+                                // existing one, otherwise append a new note.
+                                match app.editing_index.take() {
+                                    Some(index) => app.state_notes.stateful.items[index] = note,
+                                    None => app.add_note(note),
+                                }
+                                app.refresh_tabs();
+
+                                app.viewer_actions.cursor = 0;
+                                app.viewer_actions.stateful.unselect();
+                                Transition::Switch(Windows::ACTIONS)
+                            }
+                            _ => Transition::Stay,
+                        }
+                    } else {
+                        Transition::Stay
+                    }
+                }
+                KeyCode::Up => {
+                    app.viewer_actions.stateful.previous();
+                    Transition::Stay
+                }
+                KeyCode::Down => {
+                    app.viewer_actions.stateful.next();
+                    Transition::Stay
+                }
+                KeyCode::Esc => {
+                    app.viewer_actions.stateful.unselect();
+                    Transition::Switch(Windows::ACTIONS)
+                }
+                KeyCode::Char('q') => Transition::Quit,
+                _ => Transition::Stay,
+            },
+            InputMode::Editing => {
+                let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                match key.code {
+                    KeyCode::Char('z') if ctrl => app.viewer_actions.undo(),
+                    KeyCode::Char('y') | KeyCode::Char('r') if ctrl => app.viewer_actions.redo(),
+                    KeyCode::Char('o') if ctrl => {
+                        app.viewer_actions.break_group();
+                        app.viewer_actions.input_mode = InputMode::Command;
+                    }
+                    KeyCode::Enter => app.viewer_actions.insert('\n'),
+                    KeyCode::Char(c) => app.viewer_actions.insert(c),
+                    KeyCode::Backspace => app.viewer_actions.backspace(),
+                    KeyCode::Left => app.viewer_actions.move_left(),
+                    KeyCode::Right => app.viewer_actions.move_right(),
+                    KeyCode::Up => app.viewer_actions.move_up(),
+                    KeyCode::Down => app.viewer_actions.move_down(),
+                    KeyCode::Home => app.viewer_actions.home(),
+                    KeyCode::End => app.viewer_actions.end(),
+                    KeyCode::Esc => {
+                        app.viewer_actions.break_group();
+                        app.viewer_actions.input_mode = InputMode::Normal;
+                    }
+                    _ => {}
+                }
+                Transition::Stay
+            }
+            // Vim-style command sub-mode, entered from `Editing` with Ctrl+o so
+            // Esc keeps its established Editing→Normal exit flow. Holds the caret
+            // motions that would otherwise be typed as literal text.
+            InputMode::Command => {
+                match key.code {
+                    KeyCode::Char('i') | KeyCode::Enter => {
+                        app.viewer_actions.input_mode = InputMode::Editing
+                    }
+                    KeyCode::Char('0') => app.viewer_actions.home(),
+                    KeyCode::Char('^') => app.viewer_actions.first_non_whitespace(),
+                    KeyCode::Char('$') => app.viewer_actions.end(),
+                    KeyCode::Char('h') | KeyCode::Left => app.viewer_actions.move_left(),
+                    KeyCode::Char('l') | KeyCode::Right => app.viewer_actions.move_right(),
+                    KeyCode::Char('k') | KeyCode::Up => app.viewer_actions.move_up(),
+                    KeyCode::Char('j') | KeyCode::Down => app.viewer_actions.move_down(),
+                    KeyCode::Esc => app.viewer_actions.input_mode = InputMode::Editing,
+                    _ => {}
+                }
+                Transition::Stay
+            }
+        }
+    }
+
+    fn render<B: Backend>(&self, f: &mut Frame<B>, app: &App, area: Rect) {
+        let paragraph = Paragraph::new(app.viewer_actions.input.as_ref())
+            .style(match app.viewer_actions.input_mode {
+                InputMode::Normal => Style::default(),
+                InputMode::Editing => Style::default().fg(Color::Yellow),
+                InputMode::Command => Style::default().fg(Color::Cyan),
+            })
+            .wrap(Wrap { trim: true })
+            .block(create_block("New note".to_string()))
+            .alignment(tui::layout::Alignment::Left);
+
+        f.render_widget(paragraph, area);
+
+        match app.viewer_actions.input_mode {
+            InputMode::Normal =>
+                // Hide the cursor. `Frame` does this by default, so we don't need to do anything here
+                {}
+
+            InputMode::Editing | InputMode::Command => {
+                // Derive the on-screen caret position from the real byte offset.
+                let before = &app.viewer_actions.input[..app.viewer_actions.cursor];
+                let row = before.matches('\n').count();
+                let col = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+                // ! This does not account for line wrapping. Only linebreaks.
+                // Make the cursor visible and ask tui-rs to put it at the specified coordinates after rendering
+                f.set_cursor(
+                    // One column in from the border, at the caret's column
+                    area.x + col as u16 + 1,
+                    // One row down from the border, at the caret's line
+                    area.y + row as u16 + 1,
+                )
+            }
+        }
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     // setup terminal
+    // Install a panic hook that restores the terminal before printing the
+    // backtrace, so a crash anywhere inside `run_app`/`ui` doesn't leave the
+    // user stuck in raw mode on the alternate screen.
+    let original_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        original_hook(info);
+    }));
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -246,128 +1014,52 @@ fn run_app<B: Backend>(
     mut app: App,
     tick_rate: Duration,
 ) -> io::Result<()> {
-    let mut last_tick = Instant::now();
-    loop {
-        terminal.draw(|f| ui(f, &mut app))?;
-
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-        if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                // todo: move each input_handler to it's widget struct
-                match app.active_window {
-                    Windows::ACTIONS => match key.code {
-                        KeyCode::Char('q') => return app.quit(),
-                        KeyCode::Esc => return app.quit(),
-                        KeyCode::Down => app.menu_actions.stateful.next(),
-                        KeyCode::Up => app.menu_actions.stateful.previous(),
-                        KeyCode::Right => {
-                            if app.state_notes.stateful.items.len() > 0 {
-                                app.state_notes.stateful.select_first();
-                                app.active_window = Windows::NOTES;
-                                app.menu_actions.stateful.unselect();
-                            }
-                        }
-                        KeyCode::Enter => {
-                            if let Some(current) = app.menu_actions.stateful.selected() {
-                                match current {
-                                    0 => {
-                                        app.active_window = Windows::WRITER;
-                                        app.viewer_actions.stateful.select_first();
-                                    }
-                                    1 => return app.quit(),
-                                    _ => todo!(),
-                                }
-                            }
-                        }
-                        _ => {}
-                    },
-                    Windows::NOTES => match key.code {
-                        KeyCode::Char('q') => return app.quit(),
-                        KeyCode::Up => app.state_notes.stateful.previous(),
-                        KeyCode::Down => app.state_notes.stateful.next(),
-                        KeyCode::Left => {
-                            app.menu_actions.stateful.select_first();
-                            app.active_window = Windows::ACTIONS;
-                            app.state_notes.stateful.unselect();
-                        }
-                        _ => {}
-                    },
-                    Windows::WRITER => match app.viewer_actions.input_mode {
-                        InputMode::Normal => match key.code {
-                            KeyCode::Enter => {
-                                if let Some(current) = app.viewer_actions.stateful.selected() {
-                                    match current {
-                                        0 => {
-                                            app.active_window = Windows::WRITER;
-                                            app.viewer_actions.input_mode = InputMode::Editing;
-                                        }
-                                        1 => {
-                                            app.viewer_actions.input.clear();
-                                            app.viewer_actions.stateful.unselect();
-                                            app.viewer_actions.input.clear();
-                                            app.active_window = Windows::ACTIONS;
-                                        }
-                                        2 => {
-                                            let dt = Utc::now();
-                                            let timestamp = dt.format("%F %T").to_string();
-
-                                            let text: String =
-                                                app.viewer_actions.input.drain(..).collect();
-                                            let mut lines: VecDeque<&str> =
-                                                text.split('\n').collect();
-
-                                            let title = match lines.pop_front() {
-                                                Some(t) => String::from(t),
-                                                None => "New note".to_string(),
-                                            };
-
-                                            let text: String = lines.drain(..).collect();
-
-                                            app.add_note(Note {
-                                                title,
-                                                text,
-                                                timestamp,
-                                            });
-
-                                            app.viewer_actions.stateful.unselect();
-                                            app.active_window = Windows::ACTIONS;
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                            KeyCode::Up => app.viewer_actions.stateful.previous(),
-                            KeyCode::Down => app.viewer_actions.stateful.next(),
-                            KeyCode::Esc => {
-                                app.viewer_actions.stateful.unselect();
-                                app.active_window = Windows::ACTIONS;
-                            }
-                            KeyCode::Char('q') => return app.quit(),
-                            _ => {}
-                        },
-                        InputMode::Editing => match key.code {
-                            KeyCode::Enter => {
-                                app.viewer_actions.input.push('\n');
-                            }
-                            KeyCode::Char(c) => {
-                                app.viewer_actions.input.push(c);
-                            }
-                            KeyCode::Backspace => {
-                                app.viewer_actions.input.pop();
-                            }
-                            KeyCode::Esc => {
-                                app.viewer_actions.input_mode = InputMode::Normal;
-                            }
-                            _ => {}
-                        },
-                    },
+    // Spawn a dedicated input thread so blocking reads never stall the render
+    // path. It forwards key presses as `Event::Input` and emits an `Event::Tick`
+    // whenever the tick interval elapses, whether or not a key arrived.
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut last_tick = Instant::now();
+        loop {
+            let timeout = tick_rate
+                .checked_sub(last_tick.elapsed())
+                .unwrap_or_else(|| Duration::from_secs(0));
+            if event::poll(timeout).unwrap_or(false) {
+                if let Ok(CrosstermEvent::Key(key)) = event::read() {
+                    if tx.send(Event::Input(key)).is_err() {
+                        return;
+                    }
                 }
             }
+            if last_tick.elapsed() >= tick_rate {
+                if tx.send(Event::Tick).is_err() {
+                    return;
+                }
+                last_tick = Instant::now();
+            }
         }
-        if last_tick.elapsed() >= tick_rate {
-            last_tick = Instant::now();
+    });
+
+    loop {
+        terminal.draw(|f| ui(f, &mut app))?;
+
+        let key = match rx.recv() {
+            Ok(Event::Input(key)) => key,
+            Ok(Event::Tick) => continue,
+            Err(_) => return Ok(()),
+        };
+
+        // Dispatch to the active window's own handler and act on the
+        // transition it reports.
+        let transition = match app.active_window {
+            Windows::ACTIONS => ActionsWindow.handle_key(key, &mut app),
+            Windows::NOTES => NotesWindow.handle_key(key, &mut app),
+            Windows::WRITER => WriterWindow.handle_key(key, &mut app),
+        };
+        match transition {
+            Transition::Stay => {}
+            Transition::Switch(window) => app.active_window = window,
+            Transition::Quit => return app.quit(),
         }
     }
 }
@@ -426,12 +1118,31 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     f.render_stateful_widget(items, chunks[0], state);
 
-    let notes: Vec<ListItem> = app
-        .state_notes
-        .stateful
-        .items
+    // Split the notes pane into a tabs bar and the list of the selected tab.
+    let notes_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(right_chunk[0]);
+
+    let tab_titles = app
+        .tabs
+        .titles
         .iter()
-        .map(|note| {
+        .map(|t| Spans::from(Span::styled(t.clone(), Style::default().fg(Color::Blue))))
+        .collect();
+
+    let tabs = Tabs::new(tab_titles)
+        .block(Block::default().borders(Borders::ALL).title("Categories"))
+        .select(app.tabs.index)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(Color::White));
+
+    f.render_widget(tabs, notes_chunks[0]);
+
+    let visible = app.visible_indices();
+    let notes: Vec<ListItem> = visible
+        .iter()
+        .map(|&i| {
+            let note = &app.state_notes.stateful.items[i];
             let header = Spans::from(vec![
                 Span::styled(
                     format!("{:<9}", note.title),
@@ -461,76 +1172,19 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
     f.render_stateful_widget(
         notes_list,
-        right_chunk[0],
+        notes_chunks[1],
         &mut app.state_notes.stateful.state,
     );
 
-    let create_block = |title: String| Block::default().title(title).borders(Borders::ALL);
-
-    let paragraph = match app.active_window {
-        Windows::ACTIONS => {
-            let text = vec![
-                Spans::from(Span::styled(
-                    "How to navigate the app:",
-                    Style::default().fg(Color::Red),
-                )),
-                Spans::from(""),
-                Spans::from("Use the up and down arrow keys to scroll the lists"),
-                Spans::from(
-                    "Use the left and right arrow keys to switch from the Action and Notes screen",
-                ),
-                Spans::from("Press enter to press a button"),
-                Spans::from(""),
-                Spans::from(Span::styled(
-                    "Press 'q' to quit the app or use the quit button",
-                    Style::default().fg(Color::Red),
-                )),
-            ];
-
-            Paragraph::new(text)
-                .style(Style::default())
-                .wrap(Wrap { trim: true })
-                .block(create_block("Info".to_string()))
-                .alignment(tui::layout::Alignment::Left)
-        }
-        Windows::NOTES => {
-            let note =
-                &app.state_notes.stateful.items[app.state_notes.stateful.selected().unwrap()];
-
-            Paragraph::new(String::from(&note.text))
-                .style(Style::default())
-                .wrap(Wrap { trim: true })
-                .block(create_block(String::from(&note.title)))
-                .alignment(tui::layout::Alignment::Left)
-        }
-        Windows::WRITER => Paragraph::new(app.viewer_actions.input.as_ref())
-            .style(match app.viewer_actions.input_mode {
-                InputMode::Normal => Style::default(),
-                InputMode::Editing => Style::default().fg(Color::Yellow),
-            })
-            .wrap(Wrap { trim: true })
-            .block(create_block("New note".to_string()))
-            .alignment(tui::layout::Alignment::Left),
-    };
-
-    f.render_widget(paragraph, chunks[1]);
-
-    match app.viewer_actions.input_mode {
-        InputMode::Normal =>
-            // Hide the cursor. `Frame` does this by default, so we don't need to do anything here
-            {}
-
-        InputMode::Editing => {
-            let splits: Vec<&str> = app.viewer_actions.input.split('\n').collect();
-
-            // ! This does not account for line wrapping. Only linebreaks.
-            // Make the cursor visible and ask tui-rs to put it at the specified coordinates after rendering
-            f.set_cursor(
-                // Put cursor past the end of the input text
-                chunks[1].x + splits[splits.len() - 1].len() as u16 + 1,
-                // Move one line down, from the border to the input line
-                chunks[1].y + splits.len() as u16,
-            )
-        }
+    // Each window owns its detail pane; dispatch to the active one's render.
+    match app.active_window {
+        Windows::ACTIONS => ActionsWindow.render(f, app, chunks[1]),
+        Windows::NOTES => NotesWindow.render(f, app, chunks[1]),
+        Windows::WRITER => WriterWindow.render(f, app, chunks[1]),
     }
 }
+
+/// Bordered block with the given title, shared by every window's detail pane.
+fn create_block(title: String) -> Block<'static> {
+    Block::default().title(title).borders(Borders::ALL)
+}